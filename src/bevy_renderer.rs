@@ -1,27 +1,66 @@
-use crate::game::{self, Game};
+use crate::game::{self, Game, GameConfig};
 // --- Bevy Renderer ---
 use crate::Cli;
 use bevy::prelude::*;
+use std::collections::HashMap;
 use std::time::Duration;
 
 #[derive(Component)]
-struct CellSprite(usize); // Holds the index of the cell in the Game struct
+struct CellSprite(usize); // Holds the index of the cell in the DenseGame
 
 #[derive(Resource)]
 struct GameColors {
     background: Color,
+    /// The color newly-painted cells are given; hot-reloadable via `--config`
+    /// since mouse-painting reads it live (unlike the initial generation's
+    /// color, which is baked in at startup).
+    cell: Color,
 }
 
-pub fn run(cli: Cli) {
-    let game_width = cli.width.unwrap_or(120);
-    let game_height = cli.height.unwrap_or(80);
+/// The grid dimensions used to center sprites on screen, computed once at
+/// startup (and, for `--grid sparse --unbounded`, not a hard boundary).
+#[derive(Resource)]
+struct GridDims {
+    width: usize,
+    height: usize,
+}
+
+/// Tracks the sprite entity spawned for each live cell in the sparse backend,
+/// since (unlike the dense backend) there's no fixed index to pre-spawn sprites for.
+#[derive(Resource, Default)]
+struct SparseSprites(HashMap<(i64, i64), Entity>);
+
+/// The receiving end of the `--config` file watcher, present only when
+/// `--config` was given. Wrapped in a `Mutex` purely so the type is `Sync`,
+/// as required of a Bevy resource; only `apply_config_updates` ever locks it.
+#[derive(Resource)]
+struct ConfigChannel(std::sync::Mutex<std::sync::mpsc::Receiver<crate::config::HotConfig>>);
+
+/// Whether the simulation tick is currently paused. Toggled with Space;
+/// `game_tick` is gated on this, and `.` single-steps while it's set.
+#[derive(Resource, Default)]
+struct Paused(bool);
+
+/// The current tick rate, adjusted at runtime with `+`/`-`. Kept separately
+/// from `Time::<Fixed>` since that resource only stores a duration, not the
+/// fps we'd need to read back to compute the next step.
+#[derive(Resource)]
+struct SimSpeed {
+    fps: f32,
+}
+
+pub fn run(cli: Cli) -> anyhow::Result<()> {
+    let (game_width, game_height, pattern) = crate::resolve_grid(&cli, 120, 80)?;
     let cell_size = cli.cell_size;
 
     let initial_cell_color = parse_color(&cli.cell_color).unwrap_or(Color::WHITE);
     let bg_color = parse_color(&cli.bg_color).unwrap_or(Color::BLACK);
+    let rule = game::Rule::parse(&cli.rule)?;
+
+    let config_path = cli.config.clone();
 
-    App::new()
-        .add_plugins(
+    let mut app = App::new();
+    app.add_plugins(
             DefaultPlugins.set(WindowPlugin {
                 primary_window: Some(Window {
                     title: "Bevy Game of Life".into(),
@@ -38,23 +77,45 @@ pub fn run(cli: Cli) {
         .insert_resource(ClearColor(bg_color))
         .insert_resource(GameColors {
             background: bg_color,
+            cell: initial_cell_color,
         })
+        .insert_resource(GridDims { width: game_width, height: game_height })
+        .insert_resource(SparseSprites::default())
+        .insert_resource(Paused::default())
+        .insert_resource(SimSpeed { fps: cli.fps })
         .insert_resource(Time::<Fixed>::from_duration(Duration::from_secs_f32(
             1.0 / cli.fps,
         )))
-        .insert_resource(Game::new(
-            game_width,
-            game_height,
+        .insert_resource(Game::new(GameConfig {
+            width: game_width,
+            height: game_height,
             cell_size,
-            cli.initial_density,
-            initial_cell_color,
-            cli.genesis_interval,
-            cli.genesis_cluster_size,
-            cli.genesis_density,
-        ))
+            initial_density: cli.initial_density,
+            initial_color: initial_cell_color,
+            genesis_interval: cli.genesis_interval,
+            genesis_cluster_size: cli.genesis_cluster_size,
+            genesis_density: cli.genesis_density,
+            pattern,
+            rule,
+            inheritance: cli.inheritance,
+            fade_ticks: cli.fade_ticks,
+            backend: cli.grid,
+            bounded: !cli.unbounded,
+        }))
+        .insert_resource(cli)
         .add_systems(Startup, setup)
-        .add_systems(FixedUpdate, (game_tick, update_visuals).chain())
-        .run();
+        .add_systems(
+            FixedUpdate,
+            (apply_config_updates, game_tick, update_visuals_dense, update_visuals_sparse).chain(),
+        )
+        .add_systems(Update, (save_on_keypress, input));
+
+    if let Some(path) = config_path {
+        app.insert_resource(ConfigChannel(std::sync::Mutex::new(crate::config::watch(path))));
+    }
+
+    app.run();
+    Ok(())
 }
 
 fn parse_color(s: &str) -> std::result::Result<Color, ()> {
@@ -94,9 +155,11 @@ fn parse_color(s: &str) -> std::result::Result<Color, ()> {
 fn setup(mut commands: Commands, game: Res<Game>) {
     commands.spawn(Camera2d);
 
-    let game_width = game.width;
-    let game_height = game.height;
-    let cell_size = game.cell_size;
+    // The dense backend has a fixed cell count, so pre-spawn one sprite per
+    // cell and just recolor them each tick; the sparse backend spawns sprites
+    // on demand in `update_visuals_sparse` since its live set can move and grow.
+    let Game::Dense(dense) = &*game else { return };
+    let (game_width, game_height, cell_size) = (dense.width, dense.height, dense.cell_size);
 
     let cell_sprite = Sprite {
         color: Color::BLACK, // Will be updated in the first frame
@@ -120,20 +183,178 @@ fn setup(mut commands: Commands, game: Res<Game>) {
     }
 }
 
-fn game_tick(mut game: ResMut<Game>) {
-    game.tick();
+fn game_tick(mut game: ResMut<Game>, paused: Res<Paused>) {
+    if !paused.0 {
+        game.tick();
+    }
 }
 
-fn update_visuals(
+/// Mouse painting/erasing plus simulation controls: Space pauses/resumes,
+/// `.` single-steps while paused, `+`/`-` adjust the tick rate.
+fn input(
+    mut game: ResMut<Game>,
+    mut paused: ResMut<Paused>,
+    mut speed: ResMut<SimSpeed>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    dims: Res<GridDims>,
+    cli: Res<Cli>,
+    colors: Res<GameColors>,
+) {
+    if keys.just_pressed(KeyCode::Space) {
+        paused.0 = !paused.0;
+    }
+    if paused.0 && keys.just_pressed(KeyCode::Period) {
+        game.tick();
+    }
+    if keys.just_pressed(KeyCode::Equal) || keys.just_pressed(KeyCode::NumpadAdd) {
+        speed.fps = (speed.fps * 1.25).min(240.0);
+        *fixed_time = Time::<Fixed>::from_duration(Duration::from_secs_f32(1.0 / speed.fps));
+    }
+    if keys.just_pressed(KeyCode::Minus) || keys.just_pressed(KeyCode::NumpadSubtract) {
+        speed.fps = (speed.fps * 0.8).max(1.0);
+        *fixed_time = Time::<Fixed>::from_duration(Duration::from_secs_f32(1.0 / speed.fps));
+    }
+
+    let painting = buttons.pressed(MouseButton::Left);
+    let erasing = buttons.pressed(MouseButton::Right);
+    if !painting && !erasing {
+        return;
+    }
+
+    let Ok(window) = windows.single() else { return };
+    let Some(cursor) = window.cursor_position() else { return };
+    let Ok((camera, camera_transform)) = cameras.single() else { return };
+    let Ok(world_pos) = camera.viewport_to_world_2d(camera_transform, cursor) else { return };
+
+    // Inverse of `setup`'s `(cell - grid/2) * cell_size` cell-to-world math.
+    // Sprites are centered on that point, so round to the nearest cell.
+    let cell_x = (world_pos.x / cli.cell_size + dims.width as f32 / 2.0).round() as i64;
+    let cell_y = (world_pos.y / cli.cell_size + dims.height as f32 / 2.0).round() as i64;
+
+    if painting {
+        game.paint_cell(cell_x, cell_y, colors.cell);
+    } else {
+        game.erase_cell(cell_x, cell_y);
+    }
+}
+
+/// Drains the `--config` file watcher (if any) and applies each update in
+/// order: background color, cell color, tick rate, genesis parameters, and
+/// ruleset.
+fn apply_config_updates(
+    channel: Option<Res<ConfigChannel>>,
+    mut game: ResMut<Game>,
+    mut colors: ResMut<GameColors>,
+    mut clear_color: ResMut<ClearColor>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+    mut speed: ResMut<SimSpeed>,
+) {
+    let Some(channel) = channel else { return };
+    let Ok(receiver) = channel.0.lock() else { return };
+
+    for update in receiver.try_iter() {
+        if let Some(bg) = update.bg_color.as_deref().and_then(|s| parse_color(s).ok()) {
+            colors.background = bg;
+            *clear_color = ClearColor(bg);
+        }
+        if let Some(cell) = update.cell_color.as_deref().and_then(|s| parse_color(s).ok()) {
+            colors.cell = cell;
+        }
+        if let Some(fps) = update.fps {
+            speed.fps = fps;
+            *fixed_time = Time::<Fixed>::from_duration(Duration::from_secs_f32(1.0 / fps));
+        }
+        if update.genesis_interval.is_some()
+            || update.genesis_cluster_size.is_some()
+            || update.genesis_density.is_some()
+        {
+            game.set_genesis(update.genesis_interval, update.genesis_cluster_size, update.genesis_density);
+        }
+        if let Some(rule_spec) = &update.rule {
+            match game::Rule::parse(rule_spec) {
+                Ok(rule) => game.set_rule(rule),
+                Err(e) => error!("Invalid rule {rule_spec:?} in --config: {e}"),
+            }
+        }
+    }
+}
+
+fn update_visuals_dense(
     game: Res<Game>,
     colors: Res<GameColors>,
     mut query: Query<(&mut Sprite, &CellSprite)>,
 ) {
-    // This is now much more powerful, as it can render any color.
+    let Game::Dense(dense) = &*game else { return };
     for (mut sprite, cell) in query.iter_mut() {
-        sprite.color = match game.cells[cell.0] {
-            Some(cell_color) => cell_color, // Use the cell's actual color
-            None => colors.background,      // Use the background color if dead
-        };
+        sprite.color = dense.display_color(cell.0, colors.background);
+    }
+}
+
+/// Spawns/recolors/despawns one sprite per live cell, since the sparse
+/// backend has no fixed index to pre-spawn sprites for.
+fn update_visuals_sparse(
+    mut commands: Commands,
+    game: Res<Game>,
+    dims: Res<GridDims>,
+    cli: Res<Cli>,
+    mut sprite_index: ResMut<SparseSprites>,
+    mut query: Query<&mut Sprite>,
+) {
+    let Game::Sparse(sparse) = &*game else { return };
+    let cell_size = cli.cell_size;
+    let (half_width, half_height) = (dims.width as f32 / 2.0, dims.height as f32 / 2.0);
+
+    let mut still_alive = std::collections::HashSet::new();
+    for (pos, color) in sparse.live_cells() {
+        still_alive.insert(pos);
+        if let Some(&entity) = sprite_index.0.get(&pos) {
+            if let Ok(mut sprite) = query.get_mut(entity) {
+                sprite.color = color;
+            }
+        } else {
+            let entity = commands
+                .spawn((
+                    Sprite {
+                        color,
+                        custom_size: Some(Vec2::new(cell_size, cell_size)),
+                        ..default()
+                    },
+                    Transform::from_xyz(
+                        (pos.0 as f32 - half_width) * cell_size,
+                        (pos.1 as f32 - half_height) * cell_size,
+                        0.0,
+                    ),
+                ))
+                .id();
+            sprite_index.0.insert(pos, entity);
+        }
+    }
+
+    sprite_index.0.retain(|pos, &mut entity| {
+        if still_alive.contains(pos) {
+            true
+        } else {
+            commands.entity(entity).despawn();
+            false
+        }
+    });
+}
+
+/// Ctrl+S saves the current generation back out as RLE, to `--pattern` if set
+/// or to `save.rle` otherwise.
+fn save_on_keypress(keys: Res<ButtonInput<KeyCode>>, game: Res<Game>, cli: Res<Cli>) {
+    let ctrl = keys.pressed(KeyCode::ControlLeft) || keys.pressed(KeyCode::ControlRight);
+    if !(ctrl && keys.just_pressed(KeyCode::KeyS)) {
+        return;
+    }
+
+    let path = cli.pattern.clone().unwrap_or_else(|| "save.rle".into());
+    match game.save_pattern(&path) {
+        Ok(()) => info!("Saved pattern to {}", path.display()),
+        Err(e) => error!("Failed to save pattern to {}: {e}", path.display()),
     }
 }
@@ -0,0 +1,59 @@
+//! Watches a `--config` TOML file for changes and streams hot-reloadable
+//! settings over a channel, so a running simulation can be retuned without a
+//! restart (mirroring the live color-reloading some terminal emulators do).
+
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+/// The subset of [`crate::Cli`] that can be changed while the simulation is
+/// running. Every field is optional so a config file only needs to mention
+/// what it wants to override. `cell_color` is included despite only seeding
+/// newborn genetics at startup, because it's also read live by mouse-painting
+/// in the Bevy renderer.
+#[derive(Deserialize, Default, Clone)]
+pub struct HotConfig {
+    pub bg_color: Option<String>,
+    pub cell_color: Option<String>,
+    pub fps: Option<f32>,
+    pub genesis_interval: Option<u32>,
+    pub genesis_cluster_size: Option<u32>,
+    pub genesis_density: Option<f32>,
+    pub rule: Option<String>,
+}
+
+impl HotConfig {
+    fn load(path: &Path) -> anyhow::Result<HotConfig> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(toml::from_str(&contents)?)
+    }
+}
+
+/// Spawns a background thread that polls `path`'s modification time and sends
+/// a freshly-parsed [`HotConfig`] down the returned channel whenever it
+/// changes, including once immediately for the initial load.
+pub fn watch(path: PathBuf) -> Receiver<HotConfig> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut last_modified = None;
+        loop {
+            if let Ok(metadata) = std::fs::metadata(&path) {
+                let modified = metadata.modified().ok();
+                if modified != last_modified {
+                    last_modified = modified;
+                    match HotConfig::load(&path) {
+                        Ok(config) => {
+                            if tx.send(config).is_err() {
+                                return;
+                            }
+                        }
+                        Err(e) => eprintln!("Failed to load config {}: {e}", path.display()),
+                    }
+                }
+            }
+            std::thread::sleep(Duration::from_millis(500));
+        }
+    });
+    rx
+}
@@ -1,5 +1,7 @@
 // --- Core Game Logic (backend-agnostic) ---
+use crate::pattern::Pattern;
 use bevy::prelude::{Color, ColorToPacked, Resource};
+use clap::ValueEnum;
 use rand::Rng;
 use rayon::prelude::*;
 use std::collections::HashMap;
@@ -9,9 +11,188 @@ pub fn get_index(width: usize, row: usize, column: usize) -> usize {
     row * width + column
 }
 
+/// A life-like cellular automaton ruleset: `born[n]`/`survive[n]` say whether a
+/// dead/live cell with `n` live neighbors becomes/stays alive.
+#[derive(Clone)]
+pub struct Rule {
+    pub born: [bool; 9],
+    pub survive: [bool; 9],
+}
+
+impl Rule {
+    /// Parses the standard `"B.../S..."` notation, e.g. `B3/S23` (Conway's Life),
+    /// `B36/S23` (HighLife), `B2/S` (Seeds), `B3678/S34678` (Day & Night).
+    pub fn parse(spec: &str) -> anyhow::Result<Rule> {
+        let mut born = [false; 9];
+        let mut survive = [false; 9];
+
+        for part in spec.split('/') {
+            let part = part.trim();
+            let (digits, set) = if let Some(digits) = part.strip_prefix(|c: char| c == 'B' || c == 'b') {
+                (digits, &mut born)
+            } else if let Some(digits) = part.strip_prefix(|c: char| c == 'S' || c == 's') {
+                (digits, &mut survive)
+            } else {
+                anyhow::bail!("rule component {part:?} must start with 'B' or 'S' (got {spec:?})");
+            };
+
+            for ch in digits.chars() {
+                let n = ch
+                    .to_digit(10)
+                    .ok_or_else(|| anyhow::anyhow!("invalid neighbor count {ch:?} in rule {spec:?}"))?
+                    as usize;
+                anyhow::ensure!(n < set.len(), "neighbor count {n} out of range in rule {spec:?}");
+                set[n] = true;
+            }
+        }
+
+        Ok(Rule { born, survive })
+    }
+
+    /// Serializes back to `"B.../S..."` notation, e.g. `B3/S23`.
+    pub fn to_spec(&self) -> String {
+        let digits = |set: &[bool; 9]| -> String {
+            set.iter()
+                .enumerate()
+                .filter_map(|(n, &alive)| alive.then(|| n.to_string()))
+                .collect()
+        };
+        format!("B{}/S{}", digits(&self.born), digits(&self.survive))
+    }
+}
+
+impl Default for Rule {
+    /// Conway's original B3/S23.
+    fn default() -> Rule {
+        Rule::parse("B3/S23").expect("default rule is valid")
+    }
+}
+
+/// How a newborn cell picks its color from its parents (all of its live
+/// neighbors, e.g. three under B3).
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum Inheritance {
+    /// Take the single most common parent color (the original behavior).
+    Majority,
+    /// Average the parent colors in HSV space (circular mean of hue), so that
+    /// e.g. red and blue parents yield magenta rather than a muddy RGB midpoint.
+    Blend,
+    /// Like `blend`, but also perturbs the resulting hue by a small random
+    /// amount so that color lineages drift over time.
+    Mutate,
+}
+
+/// Converts sRGB components in `0.0..=1.0` to `(hue_degrees, saturation, value)`.
+fn rgb_to_hsv(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * (((g - b) / delta).rem_euclid(6.0))
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+    (hue, saturation, max)
+}
+
+/// Converts `(hue_degrees, saturation, value)` back to sRGB components.
+fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> (f32, f32, f32) {
+    let c = value * saturation;
+    let h_prime = hue.rem_euclid(360.0) / 60.0;
+    let x = c * (1.0 - (h_prime % 2.0 - 1.0).abs());
+    let (r1, g1, b1) = match h_prime as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = value - c;
+    (r1 + m, g1 + m, b1 + m)
+}
+
+/// Blends `parents` in HSV space (circular mean for hue), optionally
+/// perturbing the resulting hue by a small random delta.
+pub(crate) fn blend_colors(parents: &[Color], mutate: bool) -> Color {
+    let (mut sin_sum, mut cos_sum, mut saturation_sum, mut value_sum) = (0.0, 0.0, 0.0, 0.0);
+    for color in parents {
+        let srgba = color.to_srgba();
+        let (hue, saturation, value) = rgb_to_hsv(srgba.red, srgba.green, srgba.blue);
+        sin_sum += hue.to_radians().sin();
+        cos_sum += hue.to_radians().cos();
+        saturation_sum += saturation;
+        value_sum += value;
+    }
+
+    let count = parents.len() as f32;
+    let mut hue = sin_sum.atan2(cos_sum).to_degrees().rem_euclid(360.0);
+    if mutate {
+        hue = (hue + rand::rng().random_range(-10.0..=10.0)).rem_euclid(360.0);
+    }
+
+    let (r, g, b) = hsv_to_rgb(hue, saturation_sum / count, value_sum / count);
+    Color::srgb(r, g, b)
+}
+
+/// Picks the single most common color among `colors` (majority-vote genetics).
+/// Panics if `colors` is empty; callers only use this once a birth is known to
+/// have at least one parent.
+pub(crate) fn majority_color(colors: &[Color]) -> Color {
+    let mut color_counts = HashMap::new();
+    for color in colors {
+        *color_counts
+            .entry(color.to_srgba().to_u8_array_no_alpha())
+            .or_insert(0) += 1;
+    }
+    let key = color_counts
+        .into_iter()
+        .max_by_key(|&(_, count)| count)
+        .expect("colors is non-empty")
+        .0;
+    Color::srgb_u8(key[0], key[1], key[2])
+}
+
+/// Linearly interpolates from `from` towards `to` by `t` (`0.0` = `from`, `1.0` = `to`).
+fn lerp_color(from: Color, to: Color, t: f32) -> Color {
+    let from = from.to_srgba();
+    let to = to.to_srgba();
+    Color::srgb(
+        from.red + (to.red - from.red) * t,
+        from.green + (to.green - from.green) * t,
+        from.blue + (to.blue - from.blue) * t,
+    )
+}
+
+/// A single grid cell: either alive with a color, or dead and fading from
+/// whatever color it last held (if any) towards the background.
+#[derive(Clone, Copy)]
+pub enum Cell {
+    Alive(Color),
+    Dead { faded_from: Option<Color>, since: u32 },
+}
+
+impl Cell {
+    /// A cell that has never been alive, so it renders as pure background.
+    fn blank() -> Cell {
+        Cell::Dead { faded_from: None, since: u32::MAX }
+    }
+
+    fn is_alive(&self) -> bool {
+        matches!(self, Cell::Alive(_))
+    }
+}
+
 /// Counts the number of live neighbors and collects their colors (toroidal wrapping).
 fn get_live_neighbors_info(
-    cells: &[Option<Color>],
+    cells: &[Cell],
     width: usize,
     height: usize,
     row: usize,
@@ -29,7 +210,7 @@ fn get_live_neighbors_info(
             let neighbor_row = (row + delta_row) % height;
             let neighbor_col = (column + delta_col) % width;
             let idx = get_index(width, neighbor_row, neighbor_col);
-            if let Some(color) = cells[idx] {
+            if let Cell::Alive(color) = cells[idx] {
                 count += 1;
                 colors.push(color);
             }
@@ -38,54 +219,263 @@ fn get_live_neighbors_info(
     (count, colors)
 }
 
-/// Represents the game world.
+/// Which storage backend a [`Game`] uses for its cells.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum Backend {
+    /// A flat `Vec` indexed by `row * width + column`. O(width*height) per
+    /// tick and memory, but simple and fast for small-to-medium boards.
+    Dense,
+    /// A `BTreeMap` of only the live cells, suitable for huge, mostly-empty
+    /// (optionally unbounded) boards. See [`crate::grid::SparseGame`].
+    Sparse,
+}
+
+/// Parameters needed to construct a new [`Game`]. Gathers everything that used
+/// to be passed to [`Game::new`] as a long positional argument list, since both
+/// renderers build one of these from the CLI args in the same way.
+pub struct GameConfig {
+    pub width: usize,
+    pub height: usize,
+    pub cell_size: f32,
+    pub initial_density: f32,
+    pub initial_color: Color,
+    pub genesis_interval: u32,
+    pub genesis_cluster_size: u32,
+    pub genesis_density: f32,
+    /// A pattern to seed the grid with instead of random noise, plus the
+    /// top-left offset (in cells) at which to place it.
+    pub pattern: Option<(Pattern, (usize, usize))>,
+    pub rule: Rule,
+    pub inheritance: Inheritance,
+    /// Generations a dead cell keeps fading towards the background before it's
+    /// treated as pure background. 0 disables fading (snaps to background).
+    /// Ignored by the sparse backend, which only tracks live cells.
+    pub fade_ticks: u32,
+    pub backend: Backend,
+    /// Whether the sparse backend wraps at `width`/`height` (toroidal) or lets
+    /// coordinates grow without bound. Ignored by the dense backend, which is
+    /// always toroidal.
+    pub bounded: bool,
+}
+
+/// The game world: either backend, picked by [`GameConfig::backend`].
 #[derive(Resource)]
-pub struct Game {
+pub enum Game {
+    Dense(DenseGame),
+    Sparse(crate::grid::SparseGame),
+}
+
+impl Game {
+    /// Creates a new Game instance using the backend requested in `config`.
+    pub fn new(config: GameConfig) -> Game {
+        match config.backend {
+            Backend::Dense => Game::Dense(DenseGame::new(config)),
+            Backend::Sparse => Game::Sparse(crate::grid::SparseGame::new(config)),
+        }
+    }
+
+    /// Advances the simulation by one generation.
+    pub fn tick(&mut self) {
+        match self {
+            Game::Dense(game) => game.tick(),
+            Game::Sparse(game) => game.tick(),
+        }
+    }
+
+    /// Saves the currently-live cells to `path` in RLE format.
+    pub fn save_pattern(&self, path: &std::path::Path) -> anyhow::Result<()> {
+        match self {
+            Game::Dense(game) => game.save_pattern(path),
+            Game::Sparse(game) => game.save_pattern(path),
+        }
+    }
+
+    /// All currently-live cells, as (possibly negative, for an unbounded
+    /// sparse grid) coordinates paired with their color.
+    pub fn live_cells(&self) -> Box<dyn Iterator<Item = ((i64, i64), Color)> + '_> {
+        match self {
+            Game::Dense(game) => Box::new(game.live_cells()),
+            Game::Sparse(game) => Box::new(game.live_cells()),
+        }
+    }
+
+    /// Replaces the ruleset used by future ticks, e.g. for `--config` hot-reload.
+    pub fn set_rule(&mut self, rule: Rule) {
+        match self {
+            Game::Dense(game) => game.set_rule(rule),
+            Game::Sparse(game) => game.set_rule(rule),
+        }
+    }
+
+    /// Updates whichever genesis parameters are `Some`, leaving the rest
+    /// unchanged, e.g. for `--config` hot-reload.
+    pub fn set_genesis(&mut self, interval: Option<u32>, cluster_size: Option<u32>, density: Option<f32>) {
+        match self {
+            Game::Dense(game) => game.set_genesis(interval, cluster_size, density),
+            Game::Sparse(game) => game.set_genesis(interval, cluster_size, density),
+        }
+    }
+
+    /// Paints a live cell at `(x, y)` with `color`, e.g. for mouse editing.
+    /// Out-of-bounds coordinates are silently ignored by the dense backend;
+    /// the sparse backend wraps them like any other coordinate when bounded
+    /// (the default), and has no bounds to violate with `--unbounded`.
+    pub fn paint_cell(&mut self, x: i64, y: i64, color: Color) {
+        match self {
+            Game::Dense(game) => game.paint_cell(x, y, color),
+            Game::Sparse(game) => game.paint_cell(x, y, color),
+        }
+    }
+
+    /// Erases the cell at `(x, y)`, e.g. for mouse editing.
+    pub fn erase_cell(&mut self, x: i64, y: i64) {
+        match self {
+            Game::Dense(game) => game.erase_cell(x, y),
+            Game::Sparse(game) => game.erase_cell(x, y),
+        }
+    }
+}
+
+/// The dense storage backend: a flat `Vec<Cell>` indexed by row-major position.
+pub struct DenseGame {
     pub width: usize,
     pub height: usize,
     pub cell_size: f32,
-    pub cells: Vec<Option<Color>>, // Changed from Vec<bool> to Vec<Option<Color>>
-    next_cells: Vec<Option<Color>>,
+    pub cells: Vec<Cell>,
+    next_cells: Vec<Cell>,
     genesis_interval: u32,
     genesis_cluster_size: u32,
     genesis_density: f32,
     tick_counter: u32,
+    rule: Rule,
+    inheritance: Inheritance,
+    fade_ticks: u32,
 }
 
-impl Game {
-    /// Creates a new Game instance.
-    pub fn new(
-        width: usize,
-        height: usize,
-        cell_size: f32,
-        initial_density: f32,
-        initial_color: Color,
-        genesis_interval: u32,
-        genesis_cluster_size: u32,
-        genesis_density: f32,
-    ) -> Game {
+impl DenseGame {
+    /// Creates a new DenseGame instance.
+    pub fn new(config: GameConfig) -> DenseGame {
+        let GameConfig {
+            width,
+            height,
+            cell_size,
+            initial_density,
+            initial_color,
+            genesis_interval,
+            genesis_cluster_size,
+            genesis_density,
+            pattern,
+            rule,
+            inheritance,
+            fade_ticks,
+            backend: _,
+            bounded: _,
+        } = config;
+
         let size = width * height;
-        let mut cells = vec![None; size];
-        let mut rng = rand::rng();
-        for cell in cells.iter_mut() {
-            if rng.random_bool(initial_density as f64) {
-                *cell = Some(initial_color);
+        let mut cells = vec![Cell::blank(); size];
+
+        if let Some((pattern, (offset_x, offset_y))) = pattern {
+            for (x, y) in pattern.live_cells {
+                let (col, row) = (x + offset_x, y + offset_y);
+                if col < width && row < height {
+                    cells[get_index(width, row, col)] = Cell::Alive(initial_color);
+                }
+            }
+        } else {
+            let mut rng = rand::rng();
+            for cell in cells.iter_mut() {
+                if rng.random_bool(initial_density as f64) {
+                    *cell = Cell::Alive(initial_color);
+                }
             }
         }
 
-        Game {
+        DenseGame {
             width,
             height,
             cell_size,
             cells,
-            next_cells: vec![None; size],
+            next_cells: vec![Cell::blank(); size],
             genesis_interval,
             genesis_cluster_size,
             genesis_density,
             tick_counter: 0,
+            rule,
+            inheritance,
+            fade_ticks,
+        }
+    }
+
+    /// Saves the currently-live cells to `path` in RLE format.
+    pub fn save_pattern(&self, path: &std::path::Path) -> anyhow::Result<()> {
+        let live_cells = self.cells.iter().enumerate().filter_map(|(index, cell)| {
+            cell.is_alive().then(|| (index % self.width, index / self.width))
+        });
+        Pattern::save_rle(path, self.width, self.height, &self.rule.to_spec(), live_cells)
+    }
+
+    /// The color to draw cell `index` with, fading dead cells towards `background`.
+    pub fn display_color(&self, index: usize, background: Color) -> Color {
+        match self.cells[index] {
+            Cell::Alive(color) => color,
+            Cell::Dead { faded_from: Some(color), since } if self.fade_ticks > 0 => {
+                let t = (since as f32 / self.fade_ticks as f32).min(1.0);
+                lerp_color(color, background, t)
+            }
+            Cell::Dead { .. } => background,
         }
     }
 
+    /// Replaces the ruleset used by future ticks, e.g. for `--config` hot-reload.
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    /// Updates whichever genesis parameters are `Some`, leaving the rest unchanged.
+    pub fn set_genesis(&mut self, interval: Option<u32>, cluster_size: Option<u32>, density: Option<f32>) {
+        if let Some(interval) = interval {
+            self.genesis_interval = interval;
+        }
+        if let Some(cluster_size) = cluster_size {
+            self.genesis_cluster_size = cluster_size;
+        }
+        if let Some(density) = density {
+            self.genesis_density = density;
+        }
+    }
+
+    /// Paints a live cell at `(x, y)` with `color` if it's within the grid.
+    pub fn paint_cell(&mut self, x: i64, y: i64, color: Color) {
+        if let (Ok(x), Ok(y)) = (usize::try_from(x), usize::try_from(y))
+            && x < self.width
+            && y < self.height
+        {
+            self.cells[get_index(self.width, y, x)] = Cell::Alive(color);
+        }
+    }
+
+    /// Erases the cell at `(x, y)` if it's within the grid, with no fade trail.
+    pub fn erase_cell(&mut self, x: i64, y: i64) {
+        if let (Ok(x), Ok(y)) = (usize::try_from(x), usize::try_from(y))
+            && x < self.width
+            && y < self.height
+        {
+            self.cells[get_index(self.width, y, x)] = Cell::blank();
+        }
+    }
+
+    /// All currently-alive cells, as `(x, y)` coordinates paired with their color.
+    pub fn live_cells(&self) -> impl Iterator<Item = ((i64, i64), Color)> + '_ {
+        self.cells.iter().enumerate().filter_map(move |(index, cell)| match cell {
+            Cell::Alive(color) => Some((
+                ((index % self.width) as i64, (index / self.width) as i64),
+                *color,
+            )),
+            Cell::Dead { .. } => None,
+        })
+    }
+
     /// Creates a new random cluster of life with a new random color.
     fn random_genesis(&mut self) {
         let mut rng = rand::rng();
@@ -104,7 +494,7 @@ impl Game {
             for x in 0..cluster_size {
                 if rng.random_bool(self.genesis_density as f64) {
                     let idx = get_index(self.width, start_y + y, start_x + x);
-                    self.cells[idx] = Some(new_color);
+                    self.cells[idx] = Cell::Alive(new_color);
                 }
             }
         }
@@ -124,6 +514,8 @@ impl Game {
         let width = self.width;
         let height = self.height;
         let cells = &self.cells;
+        let rule = &self.rule;
+        let inheritance = self.inheritance;
 
         self.next_cells
             .par_iter_mut()
@@ -134,38 +526,28 @@ impl Game {
                 let (live_neighbors, neighbor_colors) =
                     get_live_neighbors_info(cells, width, height, y, x);
                 let current_cell = cells[index];
+                let n = live_neighbors as usize;
 
-                *next_cell = match (current_cell, live_neighbors) {
-                    // Rule 1: Any live cell with fewer than two live neighbours dies (underpopulation).
-                    (Some(_), n) if n < 2 => None,
-                    // Rule 2: Any live cell with two or three live neighbours lives on.
-                    (Some(color), 2) | (Some(color), 3) => Some(color),
-                    // Rule 3: Any live cell with more than three live neighbours dies (overpopulation).
-                    (Some(_), n) if n > 3 => None,
-                    // Rule 4: Any dead cell with exactly three live neighbours becomes a live cell (reproduction).
-                    (None, 3) => {
-                        // 如果没有邻居，直接返回 None
-                        if neighbor_colors.is_empty() {
-                            None
+                *next_cell = match current_cell {
+                    // A live cell survives if its neighbor count is in the ruleset's S set.
+                    Cell::Alive(color) if rule.survive[n] => Cell::Alive(color),
+                    // Otherwise it dies, leaving a fading trail of the color it held.
+                    Cell::Alive(color) => Cell::Dead { faded_from: Some(color), since: 0 },
+                    // A dead (or fading) cell is born if its neighbor count is in the B set
+                    // and it has at least one live neighbor to inherit a color from (so B0
+                    // never fires).
+                    Cell::Dead { faded_from, since } => {
+                        if rule.born[n] && !neighbor_colors.is_empty() {
+                            let color = if inheritance == Inheritance::Majority {
+                                majority_color(&neighbor_colors)
+                            } else {
+                                blend_colors(&neighbor_colors, inheritance == Inheritance::Mutate)
+                            };
+                            Cell::Alive(color)
                         } else {
-                            // 1. 使用 u8 数组作为键来计数
-                            let mut color_counts = HashMap::new();
-                            for color in neighbor_colors {
-                                *color_counts
-                                    .entry(color.to_srgba().to_u8_array_no_alpha())
-                                    .or_insert(0) += 1;
-                            }
-
-                            // 2. 找到出现次数最多的字节数组
-                            color_counts
-                                .into_iter()
-                                .max_by_key(|&(_, count)| count)
-                                // 3. 将胜出的字节数组转换回 Bevy Color
-                                .map(|(key, _)| Color::srgb_u8(key[0], key[1], key[2]))
+                            Cell::Dead { faded_from, since: since.saturating_add(1) }
                         }
                     }
-                    // All other cells remain in their current state (e.g., dead cell without 3 neighbors).
-                    (otherwise, _) => otherwise,
                 };
             });
 
@@ -0,0 +1,202 @@
+//! The sparse storage backend: only live cells are tracked, in a `BTreeMap`
+//! keyed by coordinate, so huge mostly-empty (optionally unbounded) boards
+//! cost memory and per-tick work proportional to population, not area.
+
+use crate::game::{blend_colors, majority_color, GameConfig, Inheritance, Rule};
+use crate::pattern::Pattern;
+use bevy::prelude::Color;
+use rand::Rng;
+use std::collections::{BTreeMap, HashMap};
+
+pub struct SparseGame {
+    live: BTreeMap<(i64, i64), Color>,
+    width: i64,
+    height: i64,
+    bounded: bool,
+    genesis_interval: u32,
+    genesis_cluster_size: u32,
+    genesis_density: f32,
+    tick_counter: u32,
+    rule: Rule,
+    inheritance: Inheritance,
+}
+
+impl SparseGame {
+    pub fn new(config: GameConfig) -> SparseGame {
+        let GameConfig {
+            width,
+            height,
+            cell_size: _,
+            initial_density,
+            initial_color,
+            genesis_interval,
+            genesis_cluster_size,
+            genesis_density,
+            pattern,
+            rule,
+            inheritance,
+            fade_ticks: _,
+            backend: _,
+            bounded,
+        } = config;
+
+        let mut live = BTreeMap::new();
+        if let Some((pattern, (offset_x, offset_y))) = pattern {
+            for (x, y) in pattern.live_cells {
+                live.insert((x as i64 + offset_x as i64, y as i64 + offset_y as i64), initial_color);
+            }
+        } else {
+            let mut rng = rand::rng();
+            for y in 0..height {
+                for x in 0..width {
+                    if rng.random_bool(initial_density as f64) {
+                        live.insert((x as i64, y as i64), initial_color);
+                    }
+                }
+            }
+        }
+
+        SparseGame {
+            live,
+            width: width as i64,
+            height: height as i64,
+            bounded,
+            genesis_interval,
+            genesis_cluster_size,
+            genesis_density,
+            tick_counter: 0,
+            rule,
+            inheritance,
+        }
+    }
+
+    /// Wraps `(x, y)` into the initial window when `bounded`, otherwise returns
+    /// it unchanged, letting the live set grow past the initial window.
+    fn wrap(&self, x: i64, y: i64) -> (i64, i64) {
+        if self.bounded {
+            (x.rem_euclid(self.width), y.rem_euclid(self.height))
+        } else {
+            (x, y)
+        }
+    }
+
+    /// Creates a new random cluster of life with a new random color.
+    fn random_genesis(&mut self) {
+        let mut rng = rand::rng();
+        let cluster = self.genesis_cluster_size as i64;
+        if self.width <= cluster || self.height <= cluster {
+            return;
+        }
+
+        let start_x = rng.random_range(0..self.width - cluster);
+        let start_y = rng.random_range(0..self.height - cluster);
+        let new_color = Color::srgb(rng.random(), rng.random(), rng.random());
+
+        for y in 0..cluster {
+            for x in 0..cluster {
+                if rng.random_bool(self.genesis_density as f64) {
+                    self.live.insert((start_x + x, start_y + y), new_color);
+                }
+            }
+        }
+    }
+
+    /// Calculates and updates to the next generation.
+    pub fn tick(&mut self) {
+        if self.genesis_interval > 0 {
+            self.tick_counter += 1;
+            if self.tick_counter >= self.genesis_interval {
+                self.tick_counter = 0;
+                self.random_genesis();
+            }
+        }
+
+        // Scatter each live cell's contribution to its eight neighbors, so we
+        // only ever consider coordinates within one step of a live cell.
+        let mut candidates: HashMap<(i64, i64), (u8, Vec<Color>)> = HashMap::new();
+        for (&(x, y), &color) in &self.live {
+            candidates.entry((x, y)).or_default();
+            for delta_y in -1..=1i64 {
+                for delta_x in -1..=1i64 {
+                    if delta_x == 0 && delta_y == 0 {
+                        continue;
+                    }
+                    let entry = candidates.entry(self.wrap(x + delta_x, y + delta_y)).or_default();
+                    entry.0 += 1;
+                    entry.1.push(color);
+                }
+            }
+        }
+
+        let mut next = BTreeMap::new();
+        for (pos, (count, colors)) in candidates {
+            let n = (count as usize).min(8);
+            if let Some(&color) = self.live.get(&pos) {
+                if self.rule.survive[n] {
+                    next.insert(pos, color);
+                }
+            } else if self.rule.born[n] && !colors.is_empty() {
+                let color = if self.inheritance == Inheritance::Majority {
+                    majority_color(&colors)
+                } else {
+                    blend_colors(&colors, self.inheritance == Inheritance::Mutate)
+                };
+                next.insert(pos, color);
+            }
+        }
+        self.live = next;
+    }
+
+    /// Saves the currently-live cells to `path` in RLE format, relative to
+    /// their own bounding box.
+    pub fn save_pattern(&self, path: &std::path::Path) -> anyhow::Result<()> {
+        let min_x = self.live.keys().map(|&(x, _)| x).min().unwrap_or(0);
+        let min_y = self.live.keys().map(|&(_, y)| y).min().unwrap_or(0);
+        let max_x = self.live.keys().map(|&(x, _)| x).max().unwrap_or(0);
+        let max_y = self.live.keys().map(|&(_, y)| y).max().unwrap_or(0);
+        let width = (max_x - min_x + 1).max(0) as usize;
+        let height = (max_y - min_y + 1).max(0) as usize;
+
+        let live_cells = self
+            .live
+            .keys()
+            .map(|&(x, y)| ((x - min_x) as usize, (y - min_y) as usize));
+        Pattern::save_rle(path, width, height, &self.rule.to_spec(), live_cells)
+    }
+
+    /// All currently-live cells, as `(x, y)` coordinates paired with their color.
+    pub fn live_cells(&self) -> impl Iterator<Item = ((i64, i64), Color)> + '_ {
+        self.live.iter().map(|(&pos, &color)| (pos, color))
+    }
+
+    /// Replaces the ruleset used by future ticks, e.g. for `--config` hot-reload.
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    /// Updates whichever genesis parameters are `Some`, leaving the rest unchanged.
+    pub fn set_genesis(&mut self, interval: Option<u32>, cluster_size: Option<u32>, density: Option<f32>) {
+        if let Some(interval) = interval {
+            self.genesis_interval = interval;
+        }
+        if let Some(cluster_size) = cluster_size {
+            self.genesis_cluster_size = cluster_size;
+        }
+        if let Some(density) = density {
+            self.genesis_density = density;
+        }
+    }
+
+    /// Paints a live cell at `(x, y)` (wrapped like any other coordinate unless
+    /// `--unbounded`) with `color`.
+    pub fn paint_cell(&mut self, x: i64, y: i64, color: Color) {
+        let pos = self.wrap(x, y);
+        self.live.insert(pos, color);
+    }
+
+    /// Erases the cell at `(x, y)`.
+    pub fn erase_cell(&mut self, x: i64, y: i64) {
+        let pos = self.wrap(x, y);
+        self.live.remove(&pos);
+    }
+}
@@ -3,13 +3,18 @@
 // #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod bevy_renderer;
+mod config;
 mod game;
+mod grid;
+mod pattern;
 mod terminal_renderer;
 
 // --- Main App ---
+use bevy::prelude::Resource;
 use clap::{Parser, ValueEnum};
+use std::path::PathBuf;
 
-#[derive(Parser, Clone)]
+#[derive(Parser, Clone, Resource)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
     /// Choose the rendering backend
@@ -60,6 +65,54 @@ pub struct Cli {
     /// [Terminal] The scale of each cell in pixels (e.g., 2 means 2x2)
     #[arg(long, default_value_t = 2)]
     pixel_scale: u32,
+
+    /// Seed the grid from a Life pattern file (plaintext `.cells` or `.rle`)
+    /// instead of random noise. Press 's' (terminal) or Ctrl+S (Bevy) to save
+    /// the current generation back out as RLE.
+    #[arg(long)]
+    pattern: Option<PathBuf>,
+
+    /// Top-left offset, as "x,y", at which to place `--pattern`. Defaults to
+    /// centering the pattern in the grid.
+    #[arg(long)]
+    pattern_offset: Option<String>,
+
+    /// Life-like ruleset in "B.../S..." notation, e.g. "B36/S23" for HighLife
+    /// or "B2/S" for Seeds. Defaults to Conway's original B3/S23.
+    #[arg(long, default_value = "B3/S23")]
+    rule: String,
+
+    /// How a newborn cell picks its color from its parents (its live neighbors)
+    #[arg(long, value_enum, default_value_t = game::Inheritance::Majority)]
+    inheritance: game::Inheritance,
+
+    /// Generations a dead cell keeps fading towards the background color
+    /// before disappearing. 0 disables fading (cells die instantly). Ignored
+    /// by `--grid sparse`, which only tracks live cells.
+    #[arg(long, default_value_t = 10)]
+    fade_ticks: u32,
+
+    /// Cell storage backend. `sparse` tracks only live cells and is the
+    /// prerequisite for `--unbounded`
+    #[arg(long, value_enum, default_value_t = game::Backend::Dense)]
+    grid: game::Backend,
+
+    /// Use an unbounded universe (requires `--grid sparse`): coordinates grow
+    /// past the initial window instead of wrapping. Defaults to bounded (toroidal).
+    #[arg(long, conflicts_with = "bounded")]
+    unbounded: bool,
+
+    /// Use a toroidal (wrapping) universe. This is the default; the flag
+    /// exists to pair with `--unbounded`.
+    #[arg(long, conflicts_with = "unbounded")]
+    bounded: bool,
+
+    /// Watch a TOML file for live hot-reload of `bg_color`, `cell_color`,
+    /// `fps`, the genesis parameters, and `rule` while the simulation is
+    /// running, without a restart. Any field left out of the file keeps its
+    /// current value.
+    #[arg(long)]
+    config: Option<PathBuf>,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -73,6 +126,11 @@ enum Renderer {
 fn main() {
     let cli = Cli::parse();
 
+    if let Err(e) = validate_cli(&cli) {
+        eprintln!("{e}");
+        return;
+    }
+
     match cli.renderer {
         Renderer::Terminal => {
             println!("Starting terminal renderer... Press 'q' or 'Esc' to quit.");
@@ -82,7 +140,60 @@ fn main() {
         }
         Renderer::Bevy => {
             // We need to insert cli as a NonSend resource for Bevy setup
-            bevy_renderer::run(cli);
+            if let Err(e) = bevy_renderer::run(cli) {
+                eprintln!("Bevy renderer error: {}", e);
+            }
         }
     }
 }
+
+/// Rejects CLI combinations clap's own `conflicts_with`/`requires` can't
+/// express, since they depend on an enum-valued arg's specific value rather
+/// than its mere presence.
+fn validate_cli(cli: &Cli) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        !cli.unbounded || cli.grid == game::Backend::Sparse,
+        "--unbounded requires --grid sparse"
+    );
+    Ok(())
+}
+
+/// Resolves the effective grid size and the loaded `--pattern` (if any). When
+/// `--pattern` is given without explicit `--width`/`--height`, the grid is sized
+/// to fit the pattern plus a margin instead of falling back to `default_*`.
+pub(crate) fn resolve_grid(
+    cli: &Cli,
+    default_width: usize,
+    default_height: usize,
+) -> anyhow::Result<(usize, usize, Option<(pattern::Pattern, (usize, usize))>)> {
+    const PATTERN_MARGIN: usize = 4;
+
+    let Some(path) = &cli.pattern else {
+        return Ok((
+            cli.width.unwrap_or(default_width),
+            cli.height.unwrap_or(default_height),
+            None,
+        ));
+    };
+
+    let loaded = pattern::Pattern::load(path)?;
+    let width = cli.width.unwrap_or(loaded.width + PATTERN_MARGIN * 2);
+    let height = cli.height.unwrap_or(loaded.height + PATTERN_MARGIN * 2);
+
+    let offset = match &cli.pattern_offset {
+        Some(spec) => parse_pattern_offset(spec)?,
+        None => (
+            width.saturating_sub(loaded.width) / 2,
+            height.saturating_sub(loaded.height) / 2,
+        ),
+    };
+
+    Ok((width, height, Some((loaded, offset))))
+}
+
+fn parse_pattern_offset(spec: &str) -> anyhow::Result<(usize, usize)> {
+    let (x, y) = spec
+        .split_once(',')
+        .ok_or_else(|| anyhow::anyhow!("--pattern-offset must be formatted as \"x,y\""))?;
+    Ok((x.trim().parse()?, y.trim().parse()?))
+}
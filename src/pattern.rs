@@ -0,0 +1,145 @@
+//! Loading and saving Life patterns in the plaintext (`.cells`) and RLE (`.rle`) formats.
+
+use std::fs;
+use std::path::Path;
+
+/// A decoded pattern: its bounding box and the coordinates of its live cells,
+/// relative to the pattern's own top-left corner.
+pub struct Pattern {
+    pub width: usize,
+    pub height: usize,
+    pub live_cells: Vec<(usize, usize)>,
+}
+
+impl Pattern {
+    /// Loads a pattern from `path`, dispatching on file extension: `.rle` files
+    /// are parsed as run-length encoded, everything else as plaintext.
+    pub fn load(path: &Path) -> anyhow::Result<Pattern> {
+        let contents = fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("rle") => Self::parse_rle(&contents),
+            _ => Ok(Self::parse_plaintext(&contents)),
+        }
+    }
+
+    /// Parses the plaintext format: `.`/`0`/whitespace mark a dead cell, anything
+    /// else on a data row marks a live one; lines starting with `!` are comments.
+    fn parse_plaintext(contents: &str) -> Pattern {
+        let mut live_cells = Vec::new();
+        let mut width = 0;
+        let mut height = 0;
+        for line in contents.lines() {
+            if line.starts_with('!') {
+                continue;
+            }
+            width = width.max(line.len());
+            for (x, ch) in line.chars().enumerate() {
+                if ch != '.' && ch != '0' && !ch.is_whitespace() {
+                    live_cells.push((x, height));
+                }
+            }
+            height += 1;
+        }
+        Pattern { width, height, live_cells }
+    }
+
+    /// Parses the RLE format: an `x = W, y = H, rule = ...` header followed by
+    /// run-length tokens (`b` dead, `o` alive, a leading digit run-length,
+    /// `$` end-of-row, `!` end-of-pattern).
+    fn parse_rle(contents: &str) -> anyhow::Result<Pattern> {
+        let mut width = 0;
+        let mut height = 0;
+        let mut body = String::new();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('x') {
+                for field in line.split(',') {
+                    let field = field.trim();
+                    if let Some(value) = field.strip_prefix('x').map(|s| s.trim().strip_prefix('=').unwrap_or(s).trim()) {
+                        width = value.parse()?;
+                    } else if let Some(value) = field.strip_prefix('y').map(|s| s.trim().strip_prefix('=').unwrap_or(s).trim()) {
+                        height = value.parse()?;
+                    }
+                }
+                continue;
+            }
+            body.push_str(line);
+        }
+
+        let mut live_cells = Vec::new();
+        let mut x = 0usize;
+        let mut y = 0usize;
+        let mut run = String::new();
+        'tokens: for ch in body.chars() {
+            match ch {
+                '0'..='9' => run.push(ch),
+                'b' | 'o' => {
+                    let count: usize = if run.is_empty() { 1 } else { run.parse()? };
+                    run.clear();
+                    if ch == 'o' {
+                        live_cells.extend((0..count).map(|i| (x + i, y)));
+                    }
+                    x += count;
+                }
+                '$' => {
+                    let count: usize = if run.is_empty() { 1 } else { run.parse()? };
+                    run.clear();
+                    y += count;
+                    x = 0;
+                }
+                '!' => break 'tokens,
+                _ => {}
+            }
+        }
+
+        let max_x = live_cells.iter().map(|&(x, _)| x + 1).max().unwrap_or(0);
+        let max_y = live_cells.iter().map(|&(_, y)| y + 1).max().unwrap_or(0);
+        Ok(Pattern {
+            width: width.max(max_x),
+            height: height.max(max_y),
+            live_cells,
+        })
+    }
+
+    /// Serializes a set of live-cell coordinates back to the RLE format, writing
+    /// the result to `path`.
+    pub fn save_rle(
+        path: &Path,
+        width: usize,
+        height: usize,
+        rule_spec: &str,
+        live_cells: impl Iterator<Item = (usize, usize)>,
+    ) -> anyhow::Result<()> {
+        let mut grid = vec![false; width * height];
+        for (x, y) in live_cells {
+            if x < width && y < height {
+                grid[y * width + x] = true;
+            }
+        }
+
+        let mut out = format!("x = {width}, y = {height}, rule = {rule_spec}\n");
+        for y in 0..height {
+            let mut x = 0;
+            while x < width {
+                let alive = grid[y * width + x];
+                let run_start = x;
+                while x < width && grid[y * width + x] == alive {
+                    x += 1;
+                }
+                let run_len = x - run_start;
+                if run_len > 1 {
+                    out.push_str(&run_len.to_string());
+                }
+                out.push(if alive { 'o' } else { 'b' });
+            }
+            out.push_str("$\n");
+        }
+        out.push_str("!\n");
+
+        fs::write(path, out)?;
+        Ok(())
+    }
+}
@@ -1,8 +1,8 @@
 // --- 终端渲染器 ---
 use crate::bevy_renderer::parse_color;
-use crate::game::Game;
+use crate::game::{Game, GameConfig};
 use crate::{game, Cli};
-use bevy::prelude::ColorToPacked;
+use bevy::prelude::{Color, ColorToPacked};
 use crossterm::{
     cursor, event::{self, Event, KeyCode},
     terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
@@ -15,41 +15,57 @@ use viuer::{print, Config};
 
 /// 将游戏状态渲染到一个彩色的图像缓冲区。
 // 改变 2: 函数的返回类型从 GrayImage 变为 RgbImage
-fn render_to_image(game: &Game, pixel_scale: u32) -> RgbImage {
-    let img_width = game.width as u32 * pixel_scale;
-    let img_height = game.height as u32 * pixel_scale;
-    // 改变 3: 创建一个新的 RgbImage 而不是 GrayImage
-    let mut img = RgbImage::new(img_width, img_height);
-
-    for y in 0..game.height {
-        for x in 0..game.width {
-            let index = game::get_index(game.width, y, x);
-
-            // 改变 4: 核心逻辑 - 将细胞颜色转换为像素颜色
-            // 如果细胞存活 (Some(color))，则将其 Bevy Color 转换为 Rgb<u8> 像素。
-            // Bevy Color 的各通道是 0.0 到 1.0 之间的 f32，我们需要将其映射到 0 到 255 的 u8。
-            // 如果细胞死亡 (None)，则使用黑色像素。
-            let pixel = if let Some(color) = game.cells[index] {
-                Rgb(color.to_srgba().to_u8_array_no_alpha())
-            } else {
-                Rgb([0u8, 0, 0]) // 黑色
-            };
-
-            // 用计算出的像素颜色填充放大后的方块
-            for dy in 0..pixel_scale {
-                for dx in 0..pixel_scale {
-                    img.put_pixel(
-                        (x as u32 * pixel_scale) + dx,
-                        (y as u32 * pixel_scale) + dy,
-                        pixel,
-                    );
+fn render_to_image(
+    game: &Game,
+    pixel_scale: u32,
+    bg_color: Color,
+    width: usize,
+    height: usize,
+) -> RgbImage {
+    let img_width = width as u32 * pixel_scale;
+    let img_height = height as u32 * pixel_scale;
+    // 改变 3: 创建一个新的 RgbImage 而不是 GrayImage，并先填充背景色
+    // (sparse 后端只画存活细胞，未覆盖的像素需要保留背景色)
+    let bg_pixel = Rgb(bg_color.to_srgba().to_u8_array_no_alpha());
+    let mut img = RgbImage::from_pixel(img_width, img_height, bg_pixel);
+
+    match game {
+        Game::Dense(dense) => {
+            for y in 0..dense.height {
+                for x in 0..dense.width {
+                    let index = game::get_index(dense.width, y, x);
+                    // 改变 4: 核心逻辑 - 将细胞颜色（存活色或渐隐色）转换为像素颜色。
+                    // Bevy Color 的各通道是 0.0 到 1.0 之间的 f32，我们需要将其映射到 0 到 255 的 u8。
+                    let pixel = Rgb(dense
+                        .display_color(index, bg_color)
+                        .to_srgba()
+                        .to_u8_array_no_alpha());
+                    fill_block(&mut img, x, y, pixel_scale, pixel);
                 }
             }
         }
+        Game::Sparse(sparse) => {
+            for ((x, y), color) in sparse.live_cells() {
+                if x < 0 || y < 0 || x as usize >= width || y as usize >= height {
+                    continue;
+                }
+                let pixel = Rgb(color.to_srgba().to_u8_array_no_alpha());
+                fill_block(&mut img, x as usize, y as usize, pixel_scale, pixel);
+            }
+        }
     }
     img
 }
 
+/// 用指定颜色填充放大后的单元格方块。
+fn fill_block(img: &mut RgbImage, x: usize, y: usize, pixel_scale: u32, pixel: Rgb<u8>) {
+    for dy in 0..pixel_scale {
+        for dx in 0..pixel_scale {
+            img.put_pixel((x as u32 * pixel_scale) + dx, (y as u32 * pixel_scale) + dy, pixel);
+        }
+    }
+}
+
 pub fn run(cli: Cli) -> anyhow::Result<()> {
     let mut stdout = stdout();
     stdout.execute(EnterAlternateScreen)?;
@@ -57,40 +73,81 @@ pub fn run(cli: Cli) -> anyhow::Result<()> {
     terminal::enable_raw_mode()?;
 
     let (term_cols, term_rows) = terminal::size()?;
-    let game_width = cli.width.unwrap_or(term_cols as usize);
     // 考虑到终端字符通常是长方形的，乘以2可以得到一个更接近方形的渲染区域
-    let game_height = cli.height.unwrap_or((term_rows * 2) as usize);
+    let (game_width, game_height, pattern) =
+        crate::resolve_grid(&cli, term_cols as usize, (term_rows * 2) as usize)?;
 
     // 终端渲染器使用固定的白色作为初始细胞颜色
-    let initial_color = parse_color(&cli.cell_color).unwrap_or(bevy::prelude::Color::WHITE);
+    let initial_color = parse_color(&cli.cell_color).unwrap_or(Color::WHITE);
+    let mut bg_color = parse_color(&cli.bg_color).unwrap_or(Color::BLACK);
+    let rule = game::Rule::parse(&cli.rule)?;
 
-    let mut game = Game::new(
-        game_width,
-        game_height,
-        cli.cell_size,
-        cli.initial_density,
-        initial_color,
-        cli.genesis_interval,
-        cli.genesis_cluster_size,
-        cli.genesis_density,
-    );
+    let config_rx = cli.config.clone().map(crate::config::watch);
 
-    let frame_duration = Duration::from_secs_f64(1.0 / cli.fps as f64);
+    let mut game = Game::new(GameConfig {
+        width: game_width,
+        height: game_height,
+        cell_size: cli.cell_size,
+        initial_density: cli.initial_density,
+        initial_color,
+        genesis_interval: cli.genesis_interval,
+        genesis_cluster_size: cli.genesis_cluster_size,
+        genesis_density: cli.genesis_density,
+        pattern,
+        rule,
+        inheritance: cli.inheritance,
+        fade_ticks: cli.fade_ticks,
+        backend: cli.grid,
+        bounded: !cli.unbounded,
+    });
+
+    let mut frame_duration = Duration::from_secs_f64(1.0 / cli.fps as f64);
 
     loop {
         let frame_start = Instant::now();
 
-        // 处理退出事件
+        // 轮询 --config 文件监视线程，应用任何热重载的设置
+        if let Some(rx) = &config_rx {
+            for update in rx.try_iter() {
+                if let Some(color) = update.bg_color.as_deref().and_then(|s| parse_color(s).ok()) {
+                    bg_color = color;
+                }
+                if let Some(fps) = update.fps {
+                    frame_duration = Duration::from_secs_f64(1.0 / fps as f64);
+                }
+                if update.genesis_interval.is_some()
+                    || update.genesis_cluster_size.is_some()
+                    || update.genesis_density.is_some()
+                {
+                    game.set_genesis(update.genesis_interval, update.genesis_cluster_size, update.genesis_density);
+                }
+                if let Some(rule_spec) = &update.rule {
+                    match game::Rule::parse(rule_spec) {
+                        Ok(rule) => game.set_rule(rule),
+                        Err(e) => eprintln!("Invalid rule {rule_spec:?} in --config: {e}"),
+                    }
+                }
+            }
+        }
+
+        // 处理按键事件：q/Esc 退出，s 保存当前图案
         if (event::poll(Duration::from_millis(0))?)
             && let Event::Key(key) = event::read()?
-            && (key.code == KeyCode::Char('q') || key.code == KeyCode::Esc)
         {
-            break;
+            if key.code == KeyCode::Char('q') || key.code == KeyCode::Esc {
+                break;
+            }
+            if key.code == KeyCode::Char('s') {
+                let path = cli.pattern.clone().unwrap_or_else(|| "save.rle".into());
+                if let Err(e) = game.save_pattern(&path) {
+                    eprintln!("Failed to save pattern to {}: {e}", path.display());
+                }
+            }
         }
 
         game.tick();
 
-        let image = render_to_image(&game, cli.pixel_scale);
+        let image = render_to_image(&game, cli.pixel_scale, bg_color, game_width, game_height);
         // 改变 5: 将 RgbImage 包装成 DynamicImage::ImageRgb8
         let dynamic_image = DynamicImage::ImageRgb8(image);
 